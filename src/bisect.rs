@@ -0,0 +1,201 @@
+//! # Bisection
+//! Automated performance bisection: given a known-good and known-bad commit and
+//! a single command, binary-search the commit range to locate the commit that
+//! introduced a slowdown.
+//!
+//! The ordered `good..bad` range is obtained from [`util::get_commit_range`].
+//! For each probed commit the working tree is checked out with
+//! [`util::checkout`] and `hyperfine` is invoked once with `--export-json`;
+//! the mean ± stddev of its timing runs is compared against `good`'s via
+//! interval overlap rather than a single mean. A commit that is
+//! reliably slower becomes the new "bad" bound, otherwise the new "good" bound,
+//! until a single culprit commit remains.
+use crate::error::{Error, Result};
+use crate::util;
+use clap::Args;
+use log::{debug, error, info, warn};
+use std::process::Command;
+
+/// Arguments for the `bisect` subcommand.
+///
+/// The command-shaping fields mirror those of a [`crate::core::Run`] so the same
+/// `setup`/`prepare`/`cleanup`/`shell` semantics apply to the probed command.
+#[derive(Args, Debug)]
+pub struct BisectArgs {
+    /// Known-good commit/tag (fast).
+    pub good: String,
+    /// Known-bad commit/tag (slow).
+    pub bad: String,
+    /// Command to benchmark at each probed commit.
+    #[clap(long)]
+    pub command: String,
+    /// Setup step run once before the timing runs.
+    #[clap(long)]
+    pub setup: Option<String>,
+    /// Preparation step run before every timing run.
+    #[clap(long)]
+    pub prepare: Option<String>,
+    /// Cleanup step run after every timing run.
+    #[clap(long)]
+    pub cleanup: Option<String>,
+    /// Shell used to execute the command.
+    #[clap(long)]
+    pub shell: Option<String>,
+    /// Relative mean increase over the good baseline that marks a commit slow.
+    #[clap(long, default_value_t = 0.05)]
+    pub threshold: f64,
+}
+
+impl BisectArgs {
+    /// Build the `hyperfine` argument vector for a single measurement of
+    /// `command` at the currently checked out commit.
+    fn to_hyperfine(&self, json: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        if let Some(sh) = &self.shell {
+            result.push("--shell".to_string());
+            result.push(sh.clone());
+        }
+        if let Some(cmd) = &self.cleanup {
+            result.push("--cleanup".to_string());
+            result.push(cmd.clone());
+        }
+        if let Some(cmd) = &self.prepare {
+            result.push("--prepare".to_string());
+            result.push(cmd.clone());
+        }
+        if let Some(cmd) = &self.setup {
+            result.push("--setup".to_string());
+            result.push(cmd.clone());
+        }
+        result.push("--export-json".to_string());
+        result.push(json.to_string());
+        result.push(self.command.clone());
+        result
+    }
+}
+
+/// A single hyperfine measurement summarised by its mean and standard
+/// deviation, used as a crude confidence interval for overlap comparisons.
+#[derive(Clone, Copy)]
+struct Measurement {
+    mean: f64,
+    stddev: f64,
+}
+
+impl Measurement {
+    /// Classify this measurement as slower than `good`.
+    ///
+    /// A commit counts as slow only when its `mean - stddev` interval does not
+    /// overlap `good`'s `mean + stddev` interval *and* the mean exceeds the
+    /// `good` baseline by more than `threshold`, so noisy measurements whose
+    /// intervals overlap are not mistaken for regressions.
+    fn is_slower_than(&self, good: &Measurement, threshold: f64) -> bool {
+        let separated = self.mean - self.stddev > good.mean + good.stddev;
+        let beyond_threshold = self.mean > good.mean * (1.0 + threshold);
+        separated && beyond_threshold
+    }
+}
+
+/// Measure the runtime of `args.command` at the current checkout.
+///
+/// Returns `None` when the run fails (e.g. the commit does not build) so the
+/// caller can skip the commit and narrow conservatively.
+fn measure(args: &BisectArgs, dir: &std::path::Path) -> Result<Option<Measurement>> {
+    let json = dir.join("bisect.json").display().to_string();
+    let mut cmd = Command::new("hyperfine");
+    cmd.args(args.to_hyperfine(&json));
+    info!("Running: {cmd:?}");
+    let output = cmd.output()?;
+    if !output.status.success() {
+        warn!("Hyperfine run failed: {}", util::to_string(output.stderr)?);
+        return Ok(None);
+    }
+    let mut buf = String::new();
+    std::io::Read::read_to_string(&mut std::fs::File::open(&json)?, &mut buf)?;
+    let value: serde_json::Value = serde_json::from_str(&buf)?;
+    let result = &value["results"][0];
+    match (result["mean"].as_f64(), result["stddev"].as_f64()) {
+        (Some(mean), Some(stddev)) => Ok(Some(Measurement { mean, stddev })),
+        _ => Ok(None),
+    }
+}
+
+/// Locate the commit that introduced the slowdown between `good` and `bad`.
+pub(crate) fn bisect(args: &BisectArgs) -> Result<()> {
+    let current_branch = util::get_current_branch_or_id()?;
+    info!("Current branch is: {current_branch:?}");
+
+    // Ordered `good..bad` range, oldest first, so index 0 is on the good side.
+    let commits = util::get_commit_range(&args.good, &args.bad)?;
+
+    let dir = tempfile::tempdir()?;
+    let result = run_bisect(args, &commits, dir.path());
+    // Always restore the original branch before propagating any error.
+    util::checkout(current_branch)?;
+    result
+}
+
+fn run_bisect(args: &BisectArgs, commits: &[String], dir: &std::path::Path) -> Result<()> {
+    if commits.len() <= 1 {
+        info!("Commit range has no interior commits to bisect");
+        if let Some(only) = commits.first() {
+            report(only)?;
+        }
+        return Ok(());
+    }
+
+    util::checkout(args.good.clone())?;
+    let good = match measure(args, dir)? {
+        Some(m) => m,
+        None => {
+            return Err(Error::Command(
+                "Could not measure baseline at good commit".to_string(),
+            ))
+        }
+    };
+    info!("Baseline at {}: {:.4}s (±{:.4}s)", args.good, good.mean, good.stddev);
+
+    // Binary search for the first commit measurably slower than the baseline.
+    let (mut lo, mut hi) = (0usize, commits.len() - 1);
+    while lo < hi {
+        let mut mid = (lo + hi) / 2;
+        let mut probe = None;
+        // Skip commits that fail to measure, narrowing conservatively upward.
+        while mid < hi {
+            util::checkout(commits[mid].clone())?;
+            match measure(args, dir)? {
+                Some(m) => {
+                    probe = Some(m);
+                    break;
+                }
+                None => {
+                    warn!("Skipping unmeasurable commit {}", commits[mid]);
+                    mid += 1;
+                }
+            }
+        }
+        match probe {
+            Some(m) if m.is_slower_than(&good, args.threshold) => {
+                debug!("{} is slow ({:.4}s)", commits[mid], m.mean);
+                hi = mid;
+            }
+            Some(m) => {
+                debug!("{} is fast ({:.4}s)", commits[mid], m.mean);
+                lo = mid + 1;
+            }
+            None => {
+                // Every remaining candidate failed; treat `hi` as the culprit.
+                lo = hi;
+            }
+        }
+    }
+
+    report(&commits[lo])
+}
+
+/// Print the identified culprit commit and its subject line.
+fn report(commit: &str) -> Result<()> {
+    let subject = util::get_commit_subject(commit)?;
+    error!("First slow commit: {commit} {subject}");
+    Ok(())
+}
@@ -4,8 +4,9 @@
 //! A Benchmark defines the complete hyperfine setup. It consists of individual
 //! and shared configurations between Runs. A Run is defined as a single timed
 //! command by `hyperfine`.
+use crate::paths::PathTrie;
 use crate::util;
-use log::{debug, error, trace, warn};
+use log::{debug, error, info, trace, warn};
 use serde::Deserialize;
 use std::{collections::HashMap, fmt::Display, fs::File, io::Read, path::PathBuf};
 
@@ -41,9 +42,20 @@ pub trait Hyperfined {
 #[derive(Deserialize, Debug)]
 pub(crate) struct Benchmark {
     hyperfine_params: Vec<String>,
+    #[serde(default = "default_regression_threshold")]
+    regression_threshold: f64,
+    #[serde(default)]
+    format: crate::report::ReportFormat,
+    #[serde(default)]
+    report: Option<String>,
     pub(crate) run: HashMap<String, Run>,
 }
 
+/// Default relative mean regression tolerated before a run is flagged (5%).
+fn default_regression_threshold() -> f64 {
+    0.05
+}
+
 impl Display for Benchmark {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Common Settings:")?;
@@ -58,7 +70,7 @@ impl Display for Benchmark {
 
 impl Benchmark {
     /// Setup Benchmark from a toml configuration file
-    pub(crate) fn from_config(config: PathBuf) -> std::io::Result<Self> {
+    pub(crate) fn from_config(config: PathBuf) -> crate::error::Result<Self> {
         debug!("Reading configuration file: {config:?}");
         let mut f = File::open(config)?;
         let mut content = String::new();
@@ -68,6 +80,39 @@ impl Benchmark {
         let result = toml::from_str(value)?;
         Ok(result)
     }
+
+    /// Relative mean delta above which a run is reported as a regression.
+    pub(crate) fn regression_threshold(&self) -> f64 {
+        self.regression_threshold
+    }
+
+    /// Configured report format.
+    pub(crate) fn format(&self) -> crate::report::ReportFormat {
+        self.format
+    }
+
+    /// Configured report destination; `None` writes to stdout.
+    pub(crate) fn report(&self) -> Option<&str> {
+        self.report.as_deref()
+    }
+
+    /// Drop, for every run, the commits whose changes do not touch any of its
+    /// configured `paths`.
+    pub(crate) fn filter_commits_by_paths(&mut self) -> crate::error::Result<()> {
+        for (label, run) in self.run.iter_mut() {
+            run.filter_commits_by_paths(label)?;
+        }
+        Ok(())
+    }
+
+    /// Drop, for every run, WIP/fixup/squash commits and — when `commit_types`
+    /// is set — commits whose Conventional Commit type does not match.
+    pub(crate) fn filter_commits_by_message(&mut self) -> crate::error::Result<()> {
+        for (label, run) in self.run.iter_mut() {
+            run.filter_commits_by_message(label)?;
+        }
+        Ok(())
+    }
 }
 
 impl Hyperfined for Benchmark {
@@ -88,6 +133,10 @@ pub(crate) struct Run {
     setup: Option<String>,
     shell: Option<String>,
     annotations: Option<HashMap<String, String>>,
+    #[serde(default)]
+    paths: Option<Vec<String>>,
+    #[serde(default)]
+    commit_types: Option<Vec<String>>,
     command: String,
 }
 
@@ -133,7 +182,11 @@ enum Commits {
 /// Checking correctness of commit ids
 fn check_correctness_of_commit_ids(vec: &[String]) -> Commits {
     debug!("Commits: {vec:?}");
-    if vec.iter().any(|s| s == "--all") {
+    if vec.iter().any(|s| s.contains("..")) {
+        // At least one entry is a revision range; expand all entries to a
+        // concrete ordered commit list.
+        return expand_revisions(vec);
+    } else if vec.iter().any(|s| s == "--all") {
         // Benchmark should run on all commits
         return Commits::SpecialCaseAll(util::get_abbrev_commit_ids().unwrap());
     } else if vec.iter().any(|s| s == "--branches") {
@@ -182,6 +235,149 @@ fn check_correctness_of_commit_ids(vec: &[String]) -> Commits {
     }
 }
 
+impl Run {
+    /// Keep only the commits whose diff against their first parent touches one
+    /// of the configured `paths`; a run without `paths` is left untouched.
+    fn filter_commits_by_paths(&mut self, label: &str) -> crate::error::Result<()> {
+        let (paths, commits) = match (&self.paths, &mut self.commits) {
+            (Some(paths), Some(commits)) => (paths, commits),
+            _ => return Ok(()),
+        };
+        let trie = PathTrie::from_patterns(paths);
+        let before = commits.len();
+        let mut kept = Vec::with_capacity(before);
+        let mut skipped = Vec::new();
+        for commit in commits.drain(..) {
+            let touched = util::changed_files(&commit)?;
+            if touched.iter().any(|f| trie.matches(f)) {
+                kept.push(commit);
+            } else {
+                debug!("{label}: skipping {commit}, no changes under {paths:?}");
+                skipped.push(commit);
+            }
+        }
+        if !skipped.is_empty() {
+            info!(
+                "{label}: skipped {}/{before} commit(s) with no changes under {paths:?}",
+                skipped.len()
+            );
+            for commit in &skipped {
+                let subject = util::get_commit_subject(commit).unwrap_or_default();
+                info!("  skipped {commit}: {subject}");
+            }
+        }
+        *commits = kept;
+        Ok(())
+    }
+
+    /// Drop WIP/fixup/squash commits and, when `commit_types` is configured,
+    /// keep only commits whose Conventional Commit type matches.
+    fn filter_commits_by_message(&mut self, label: &str) -> crate::error::Result<()> {
+        let commits = match &mut self.commits {
+            Some(commits) => commits,
+            None => return Ok(()),
+        };
+        let mut kept = Vec::with_capacity(commits.len());
+        let mut nonconforming = Vec::new();
+        for commit in commits.drain(..) {
+            let subject = util::get_commit_subject(&commit)?;
+            let lower = subject.to_lowercase();
+            if is_wip_marker(&lower) || lower.starts_with("fixup!") || lower.starts_with("squash!")
+            {
+                debug!("{label}: dropping WIP/fixup/squash commit {commit}: {subject}");
+                continue;
+            }
+            match &self.commit_types {
+                Some(types) => match parse_conventional_type(&subject) {
+                    Some(ty) if types.iter().any(|t| t == &ty) => kept.push(commit),
+                    Some(ty) => debug!("{label}: dropping {commit}, type {ty:?} not selected"),
+                    None => nonconforming.push((commit, subject)),
+                },
+                None => kept.push(commit),
+            }
+        }
+        if !nonconforming.is_empty() {
+            warn!(
+                "{label}: dropped {} commit(s) with non-conforming messages",
+                nonconforming.len()
+            );
+            for (commit, subject) in &nonconforming {
+                warn!("  {commit}: {subject}");
+            }
+        }
+        *commits = kept;
+        Ok(())
+    }
+}
+
+/// Whether a lower-cased subject is marked as work-in-progress.
+///
+/// Only a `wip` marker is matched — a bare `wip`, or a `wip` followed by a
+/// separator such as `wip:` or `wip ` — so legitimate subjects like
+/// "wipe stale cache" are kept.
+fn is_wip_marker(lower: &str) -> bool {
+    lower == "wip"
+        || lower
+            .strip_prefix("wip")
+            .is_some_and(|rest| rest.starts_with(|c: char| !c.is_alphanumeric()))
+}
+
+/// Parse the Conventional Commit type off a subject line.
+///
+/// Returns the lower-cased `type` of a `type(scope)!: description` subject, or
+/// `None` when the subject does not conform.
+fn parse_conventional_type(subject: &str) -> Option<String> {
+    let (prefix, _) = subject.split_once(':')?;
+    let ty = prefix.trim().split(|c| c == '(' || c == '!').next()?.trim();
+    if ty.is_empty() || !ty.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(ty.to_lowercase())
+}
+
+/// Expand a list that may contain revision ranges (`good..bad`) and plain refs
+/// into a concrete, ordered commit list.
+///
+/// Unresolvable range endpoints are reported individually rather than as the
+/// whole range string, and any of them makes the list invalid. The selector's
+/// original ordering is preserved.
+fn expand_revisions(vec: &[String]) -> Commits {
+    let mut expanded = Vec::new();
+    let mut not_found = Vec::new();
+    for entry in vec {
+        match entry.split_once("..") {
+            Some((good, bad)) => {
+                for endpoint in [good, bad] {
+                    if !util::rev_exists(endpoint) {
+                        not_found.push(endpoint.to_string());
+                    }
+                }
+                if util::rev_exists(good) && util::rev_exists(bad) {
+                    match util::get_commit_range(good, bad) {
+                        Ok(commits) => expanded.extend(commits),
+                        Err(e) => {
+                            error!("Could not expand range {entry}: {e}");
+                            not_found.push(entry.clone());
+                        }
+                    }
+                }
+            }
+            None => {
+                if util::rev_exists(entry) {
+                    expanded.push(entry.clone());
+                } else {
+                    not_found.push(entry.clone());
+                }
+            }
+        }
+    }
+    if not_found.is_empty() {
+        Commits::SpecialCaseAll(expanded)
+    } else {
+        Commits::SomeInvalid(not_found)
+    }
+}
+
 impl Hyperfined for Run {
     /// Return custom-part of `hyperfine` configuration of Run
     fn to_hyperfine(&self) -> Vec<String> {
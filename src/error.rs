@@ -0,0 +1,84 @@
+//! # Error
+//! Crate-wide error type.
+//!
+//! Git, I/O, (de)serialization and subprocess failures used to be funnelled
+//! into `std::io::Error::new(ErrorKind::Other, …)`, which erased their origin.
+//! [`Error`] keeps each source typed so callers get precise, contextful
+//! failures, and the `From` impls let `?` convert at the call site.
+use std::fmt;
+
+/// Convenience alias for fallible crate operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while running hypcmp.
+#[derive(Debug)]
+pub enum Error {
+    /// A libgit2 operation failed.
+    Git2(git2::Error),
+    /// An I/O operation failed.
+    Io(std::io::Error),
+    /// A TOML document could not be parsed.
+    Toml(toml::de::Error),
+    /// A JSON document could not be parsed or serialized.
+    SerdeJson(serde_json::Error),
+    /// A spawned subprocess reported failure.
+    Command(String),
+    /// Output was not valid UTF-8.
+    Utf8(std::str::Utf8Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Git2(e) => write!(f, "git error: {e}"),
+            Error::Io(e) => write!(f, "io error: {e}"),
+            Error::Toml(e) => write!(f, "toml error: {e}"),
+            Error::SerdeJson(e) => write!(f, "json error: {e}"),
+            Error::Command(msg) => write!(f, "command failed: {msg}"),
+            Error::Utf8(e) => write!(f, "utf-8 error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Git2(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Toml(e) => Some(e),
+            Error::SerdeJson(e) => Some(e),
+            Error::Utf8(e) => Some(e),
+            Error::Command(_) => None,
+        }
+    }
+}
+
+impl From<git2::Error> for Error {
+    fn from(e: git2::Error) -> Self {
+        Error::Git2(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::SerdeJson(e)
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(e: std::str::Utf8Error) -> Self {
+        Error::Utf8(e)
+    }
+}
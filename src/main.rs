@@ -1,35 +1,80 @@
+use crate::bisect::BisectArgs;
 use crate::core::Hyperfined;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::{debug, error, info, trace};
 use std::path::PathBuf;
 use std::process::Command;
 
+mod bisect;
 mod core;
+mod error;
+mod paths;
+mod report;
+mod results;
 mod util;
 
+use crate::error::{Error, Result};
+
 /// Command-line Interface (CLI) for the hypcmp library
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Configuration file [*.toml]
-    #[clap(value_parser)]
-    pub config: PathBuf,
+    #[clap(subcommand)]
+    pub command: Commands,
 
     #[clap(flatten)]
     pub verbose: clap_verbosity_flag::Verbosity,
 }
 
-fn main() -> std::io::Result<()> {
-    let config = Cli::parse();
+/// Modes of operation.
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Run the benchmarks described by a configuration file.
+    Run {
+        /// Configuration file [*.toml]
+        #[clap(value_parser)]
+        config: PathBuf,
+        /// Override the report output format from the configuration file.
+        #[clap(long, value_enum)]
+        format: Option<report::ReportFormat>,
+        /// TOML file the per-commit results history is read from and written to.
+        #[clap(long)]
+        results: Option<PathBuf>,
+    },
+    /// Locate the commit that introduced a slowdown.
+    Bisect(BisectArgs),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
     env_logger::Builder::new()
-        .filter_level(config.verbose.log_level_filter())
+        .filter_level(cli.verbose.log_level_filter())
         .init();
-    debug!("Loaded configuration: {config:?}");
+    debug!("Loaded configuration: {cli:?}");
 
     util::hyperfine_installed()?;
+
+    match cli.command {
+        Commands::Run {
+            config,
+            format,
+            results,
+        } => run(config, format, results),
+        Commands::Bisect(args) => bisect::bisect(&args),
+    }
+}
+
+/// Execute the benchmark suite described by `config`.
+fn run(
+    config: PathBuf,
+    format: Option<report::ReportFormat>,
+    results: Option<PathBuf>,
+) -> Result<()> {
     util::is_git_dirty()?;
 
-    let c = core::Benchmark::from_config(config.config)?;
+    let mut c = core::Benchmark::from_config(config)?;
+    c.filter_commits_by_message()?;
+    c.filter_commits_by_paths()?;
     trace!("Benchmark Setup: {c:#?}");
 
     let dir = tempfile::tempdir()?;
@@ -65,7 +110,7 @@ fn main() -> std::io::Result<()> {
             files_to_be_merged.push(output);
         } else {
             let msg = result.stderr;
-            let s = util::to_string(msg);
+            let s = util::to_string(msg)?;
             error!("Run {label:?} failed. Skipping...");
             error!("Hyperfine message: {}", s);
             error!("Run parameters were: {cmd:?}");
@@ -74,14 +119,34 @@ fn main() -> std::io::Result<()> {
     if files_to_be_merged.is_empty() {
         let msg = "No hyperfine benchmark run";
         error!("{msg}");
-        let err = std::io::Error::new(std::io::ErrorKind::Other, msg);
         util::checkout(current_branch)?;
-        return Err(err);
+        return Err(Error::Command(msg.to_string()));
     } else {
         let json = util::merge_json_files(&files_to_be_merged)?;
-        util::write_json_to_disk(json)?;
+
+        let commit = util::get_head_commit_id()?;
+        let commitmsg = util::get_commit_subject(&commit)?;
+        let results_path = results
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(results::DEFAULT_RESULTS_FILE));
+        let mut store = results::ResultsStore::load(&results_path)?;
+        let regressed =
+            store.record_and_compare(&json, &commit, &commitmsg, c.regression_threshold())?;
+        store.save(&results_path)?;
+
+        let rendered = report::render(&json, format.unwrap_or_else(|| c.format()))?;
+        match c.report() {
+            Some(path) => util::write_to_disk(path, &rendered)?,
+            None => util::write_to_stdout(&rendered)?,
+        }
         util::cleanup(files_to_be_merged, dir)?;
         util::checkout(current_branch)?;
+
+        if regressed {
+            let msg = "Performance regression detected";
+            error!("{msg}");
+            return Err(Error::Command(msg.to_string()));
+        }
     }
     Ok(())
 }
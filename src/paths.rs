@@ -0,0 +1,49 @@
+//! # Path matching
+//! A prefix trie over configured path components, used to decide whether a
+//! commit's changed files are relevant to a benchmark.
+//!
+//! Each configured path (e.g. `src/core`) is split into its components and
+//! inserted into the trie. A changed file matches when one of the configured
+//! paths is a component-prefix of it, so a single walk of the file's components
+//! decides inclusion in `O(path length)`. A `*` component acts as a
+//! single-component wildcard.
+use std::collections::HashMap;
+
+/// Prefix trie of configured path components.
+#[derive(Debug, Default)]
+pub(crate) struct PathTrie {
+    children: HashMap<String, PathTrie>,
+    /// Whether a configured path ends here.
+    terminal: bool,
+}
+
+impl PathTrie {
+    /// Build a trie from the configured path patterns.
+    pub(crate) fn from_patterns(patterns: &[String]) -> Self {
+        let mut root = PathTrie::default();
+        for pattern in patterns {
+            let mut node = &mut root;
+            for comp in pattern.split('/').filter(|c| !c.is_empty()) {
+                node = node.children.entry(comp.to_string()).or_default();
+            }
+            node.terminal = true;
+        }
+        root
+    }
+
+    /// Return `true` when `path` is covered by one of the configured paths.
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        let mut node = self;
+        for comp in path.split('/').filter(|c| !c.is_empty()) {
+            if node.terminal {
+                // A shorter configured path already covers this file.
+                return true;
+            }
+            match node.children.get(comp).or_else(|| node.children.get("*")) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        node.terminal
+    }
+}
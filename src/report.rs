@@ -0,0 +1,124 @@
+//! # Report
+//! Human-facing summary of a merged `hyperfine` result set.
+//!
+//! After the per-run JSON files are merged, each command's mean/stddev is
+//! reported together with its relative speedup versus the fastest command.
+//! When a `commit` parameter is present the rows are grouped by logical command
+//! (the part before the `@commit` suffix added by
+//! [`crate::util::move_commit_label_to_cmd_name`]) so each commit is compared
+//! against the fastest commit of the same command. Rows are sorted by mean
+//! ascending and the baseline is annotated as `1.00x`.
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{Error, ErrorKind};
+
+/// Output format for the comparison report.
+#[derive(Deserialize, ValueEnum, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// The merged `hyperfine` JSON, pretty-printed (default).
+    #[default]
+    Json,
+    /// A Markdown comparison table.
+    Markdown,
+    /// Comma-separated values.
+    Csv,
+}
+
+/// A single row of the comparison report.
+struct Row {
+    command: String,
+    commit: Option<String>,
+    mean: f64,
+    stddev: f64,
+}
+
+/// Render the merged result set in the requested format.
+pub(crate) fn render(merged: &Value, format: ReportFormat) -> std::io::Result<String> {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(merged).map_err(Into::into),
+        ReportFormat::Markdown => Ok(table(&rows(merged)?, Style::Markdown)),
+        ReportFormat::Csv => Ok(table(&rows(merged)?, Style::Csv)),
+    }
+}
+
+/// Extract the comparison rows from the merged JSON.
+fn rows(merged: &Value) -> std::io::Result<Vec<Row>> {
+    let results = merged["results"]
+        .as_array()
+        .ok_or_else(|| Error::new(ErrorKind::Other, "Merged json has no results array"))?;
+    let rows = results
+        .iter()
+        .map(|run| {
+            let name = run["command"].as_str().unwrap_or("<unknown>");
+            let (command, commit) = split_name(name);
+            Row {
+                command,
+                commit,
+                mean: run["mean"].as_f64().unwrap_or(f64::NAN),
+                stddev: run["stddev"].as_f64().unwrap_or(f64::NAN),
+            }
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// Split a `name@commit` label into its logical command and optional commit.
+fn split_name(name: &str) -> (String, Option<String>) {
+    match name.rsplit_once('@') {
+        Some((cmd, commit)) => (cmd.to_string(), Some(commit.to_string())),
+        None => (name.to_string(), None),
+    }
+}
+
+enum Style {
+    Markdown,
+    Csv,
+}
+
+/// Build the table, grouping by logical command and annotating speedup ratios.
+///
+/// Rows that carry a commit are grouped by logical command; rows without one
+/// form a single group compared against the fastest of them.
+fn table(rows: &[Row], style: Style) -> String {
+    let mut out = String::new();
+    match style {
+        Style::Markdown => {
+            out.push_str("| Command | Commit | Mean [s] | Stddev [s] | Ratio |\n");
+            out.push_str("|---|---|---|---|---|\n");
+        }
+        Style::Csv => out.push_str("command,commit,mean,stddev,ratio\n"),
+    }
+
+    // Preserve first-seen group order, then sort each group by mean ascending.
+    let group_of = |r: &Row| r.commit.is_some().then(|| r.command.clone());
+    let mut order: Vec<Option<String>> = Vec::new();
+    for row in rows {
+        let key = group_of(row);
+        if !order.contains(&key) {
+            order.push(key);
+        }
+    }
+
+    for key in order {
+        let mut group: Vec<&Row> = rows.iter().filter(|r| group_of(*r) == key).collect();
+        group.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        let fastest = group.first().map(|r| r.mean).unwrap_or(f64::NAN);
+        for row in group {
+            let ratio = row.mean / fastest;
+            let commit = row.commit.as_deref().unwrap_or("");
+            match style {
+                Style::Markdown => out.push_str(&format!(
+                    "| {} | {} | {:.4} | {:.4} | {:.2}x |\n",
+                    row.command, commit, row.mean, row.stddev, ratio
+                )),
+                Style::Csv => out.push_str(&format!(
+                    "{},{},{:.4},{:.4},{:.2}\n",
+                    row.command, commit, row.mean, row.stddev, ratio
+                )),
+            }
+        }
+    }
+    out
+}
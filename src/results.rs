@@ -0,0 +1,168 @@
+//! # Results store
+//! Persists the results of each benchmark run so that performance can be
+//! tracked across invocations instead of being discarded once `hyperfine`
+//! exits.
+//!
+//! Every run appends its measured data points to a TOML file keyed by the
+//! full commit hash. For each logical command the previous entry is reused
+//! as a baseline: the per-metric relative delta `(new - old) / old` is computed
+//! and printed as a comparison table. A mean that regresses by more than the
+//! configured `regression_threshold` is logged as an error so that `main` can
+//! gate CI with a non-zero exit code.
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+/// Default file the results history is read from and written to.
+pub(crate) const DEFAULT_RESULTS_FILE: &str = "hypcmp-results.toml";
+
+/// A single named measurement.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct DataPoint {
+    pub(crate) mean: f64,
+    pub(crate) stddev: f64,
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+}
+
+/// Results recorded for one commit.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct CommitResults {
+    /// Subject line of the commit, kept for human-readable reports.
+    pub(crate) commitmsg: String,
+    /// Monotonic sequence number recording the order in which commits were
+    /// measured. Since the history is a `HashMap`, this gives a well-defined
+    /// "previous" entry to pick as a baseline rather than an arbitrary one.
+    #[serde(default)]
+    pub(crate) recorded: u64,
+    /// Map of logical command name to its measured data point.
+    pub(crate) data_points: HashMap<String, DataPoint>,
+}
+
+/// Accumulated performance history, keyed by full commit hash.
+///
+/// Mirrors `HashMap<commit, {commitmsg, data_points}>` so history accumulates
+/// across runs and old entries can be reused as baselines.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct ResultsStore {
+    #[serde(flatten)]
+    pub(crate) history: HashMap<String, CommitResults>,
+}
+
+impl ResultsStore {
+    /// Load the history from disk, returning an empty store when the file does
+    /// not exist yet.
+    pub(crate) fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            debug!("No results store at {path:?}, starting with empty history");
+            return Ok(Self::default());
+        }
+        let mut f = File::open(path)?;
+        let mut buf = String::new();
+        f.read_to_string(&mut buf)?;
+        let store = toml::from_str(&buf)
+            .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+        Ok(store)
+    }
+
+    /// Persist the history back to disk.
+    pub(crate) fn save(&self, path: &Path) -> std::io::Result<()> {
+        let s = toml::to_string_pretty(self)
+            .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+        let mut f = File::create(path)?;
+        f.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    /// Look up the most recent data point for `name` in any commit other than
+    /// `current`, to be reused as a baseline. "Most recent" is the entry with
+    /// the highest `recorded` sequence number, so the choice is deterministic
+    /// even when several past commits recorded the metric.
+    fn baseline(&self, current: &str, name: &str) -> Option<DataPoint> {
+        self.history
+            .iter()
+            .filter(|(commit, _)| commit.as_str() != current)
+            .filter_map(|(_, entry)| entry.data_points.get(name).map(|p| (entry.recorded, *p)))
+            .max_by_key(|(recorded, _)| *recorded)
+            .map(|(_, point)| point)
+    }
+
+    /// Append the merged `hyperfine` results of the current run and report how
+    /// they compare against the recorded baselines.
+    ///
+    /// Prints a table sorted by relative delta (worst first) and flags any run
+    /// whose mean moved by more than `threshold` as a regression or
+    /// improvement. Returns `true` when at least one run regressed so the caller
+    /// can fail the process.
+    pub(crate) fn record_and_compare(
+        &mut self,
+        merged: &Value,
+        commit: &str,
+        commitmsg: &str,
+        threshold: f64,
+    ) -> std::io::Result<bool> {
+        let results = merged["results"]
+            .as_array()
+            .ok_or_else(|| Error::new(ErrorKind::Other, "Merged json has no results array"))?;
+
+        // Assign the next sequence number so this run is ordered after every
+        // commit already in the store; a re-measured commit keeps advancing.
+        let next = self
+            .history
+            .values()
+            .map(|e| e.recorded)
+            .max()
+            .map_or(0, |m| m + 1);
+        let mut entry = CommitResults {
+            commitmsg: commitmsg.to_string(),
+            recorded: next,
+            data_points: HashMap::new(),
+        };
+        let mut rows: Vec<(String, f64, Option<f64>)> = Vec::new();
+
+        for run in results {
+            let name = run["command"].as_str().unwrap_or("<unknown>").to_string();
+            let point = DataPoint {
+                mean: run["mean"].as_f64().unwrap_or(f64::NAN),
+                stddev: run["stddev"].as_f64().unwrap_or(f64::NAN),
+                min: run["min"].as_f64().unwrap_or(f64::NAN),
+                max: run["max"].as_f64().unwrap_or(f64::NAN),
+            };
+            let delta = self
+                .baseline(commit, &name)
+                .filter(|b| b.mean != 0.0)
+                .map(|b| (point.mean - b.mean) / b.mean);
+            rows.push((name.clone(), point.mean, delta));
+            entry.data_points.insert(name, point);
+        }
+
+        // Worst relative delta first; runs without a baseline go last.
+        rows.sort_by(|a, b| {
+            b.2.unwrap_or(f64::NEG_INFINITY)
+                .total_cmp(&a.2.unwrap_or(f64::NEG_INFINITY))
+        });
+
+        let mut regressed = false;
+        info!("Comparison against baselines for {commit}:");
+        for (name, mean, delta) in &rows {
+            match delta {
+                Some(d) if *d > threshold => {
+                    error!("  {name}: {mean:.4}s ({:+.2}%) regression", d * 100.0);
+                    regressed = true;
+                }
+                Some(d) if *d < -threshold => {
+                    info!("  {name}: {mean:.4}s ({:+.2}%) improvement", d * 100.0);
+                }
+                Some(d) => info!("  {name}: {mean:.4}s ({:+.2}%)", d * 100.0),
+                None => info!("  {name}: {mean:.4}s (no baseline)"),
+            }
+        }
+
+        self.history.insert(commit.to_string(), entry);
+        Ok(regressed)
+    }
+}
@@ -1,61 +1,84 @@
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, Repository, StatusOptions};
 use log::{debug, error, trace};
 use serde_json::Value;
 use std::fs::File;
-use std::io::{Error, ErrorKind, Read, Write};
+use std::io::{Read, Write};
 use std::process::Command;
 use tempfile::TempDir;
 
-/// Git: Checkout to specific commit
-pub(crate) fn checkout(commit: String) -> std::io::Result<()> {
+/// Open the repository discovered from the current working directory.
+fn repo() -> Result<Repository> {
+    Ok(Repository::discover(".")?)
+}
+
+/// Git: Checkout to a specific commit, tag or branch
+pub(crate) fn checkout(commit: String) -> Result<()> {
+    let repo = repo()?;
     let id = get_current_branch_or_id()?;
-    if id != commit {
-        debug!("Git state changed!");
-        let status = Command::new("git")
-            .arg("checkout")
-            .arg(commit.clone())
-            .arg("--quiet")
-            .status()?;
-        if !status.success() {
-            error!("Could not checkout from {} to {}", id, commit);
-        }
-    } else {
+    if id == commit {
         debug!("Git state not changed");
+        return Ok(());
     }
-    Ok(()) // return HEAD is detached
+    debug!("Git state changed!");
+    let object = repo.revparse_single(&commit)?;
+    // The working tree is verified clean by `is_git_dirty`, so force the
+    // checkout to actually materialise the target tree; the default strategy
+    // (`GIT_CHECKOUT_NONE`) is a dry run that leaves the files untouched.
+    repo.checkout_tree(&object, Some(CheckoutBuilder::new().force()))?;
+    // Point HEAD at the ref when one resolves, otherwise detach onto the commit.
+    match repo.resolve_reference_from_short_name(&commit) {
+        Ok(reference) => {
+            let name = reference
+                .name()
+                .ok_or_else(|| Error::Command(format!("Reference {commit} has no name")))?;
+            repo.set_head(name)?;
+        }
+        Err(_) => repo.set_head_detached(object.id())?,
+    }
+    Ok(())
 }
 
 /// Git: Get current checked out branch or commit
-pub(crate) fn get_current_branch_or_id() -> std::io::Result<String> {
-    let mut br = get_current_branch()?;
-    trim_newline(&mut br);
-    if br == "HEAD" {
-        debug!("Git not checked out at branch or tag");
-        br = get_current_commit()?;
-        debug!("Git at commit id: {br:?}");
-        trim_newline(&mut br);
-        Ok(br)
+pub(crate) fn get_current_branch_or_id() -> Result<String> {
+    let repo = repo()?;
+    let head = repo.head()?;
+    if head.is_branch() {
+        let name = head
+            .shorthand()
+            .ok_or_else(|| Error::Command("HEAD has no shorthand name".to_string()))?;
+        Ok(name.to_string())
     } else {
-        Ok(br)
+        debug!("Git not checked out at branch or tag");
+        let id = head
+            .target()
+            .ok_or_else(|| Error::Command("HEAD has no target".to_string()))?;
+        debug!("Git at commit id: {id:?}");
+        Ok(id.to_string())
     }
 }
 
-fn get_current_branch() -> std::io::Result<String> {
-    let r = Command::new("git")
-        .arg("rev-parse")
-        .arg("--abbrev-ref")
-        .arg("HEAD")
-        .output()?
-        .stdout;
-    Ok(to_string(r)) // return HEAD is detached
+/// Git: Report whether a revision (ref, tag or commit id) resolves
+pub(crate) fn rev_exists(rev: &str) -> bool {
+    match repo() {
+        Ok(repo) => repo.revparse_single(rev).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Git: Get the full commit hash that `HEAD` resolves to
+pub(crate) fn get_head_commit_id() -> Result<String> {
+    let repo = repo()?;
+    let id = repo.head()?.peel_to_commit()?.id();
+    Ok(id.to_string())
 }
 
-fn get_current_commit() -> std::io::Result<String> {
-    let r = Command::new("git")
-        .arg("rev-parse")
-        .arg("HEAD")
-        .output()?
-        .stdout;
-    Ok(to_string(r)) // return HEAD is detached
+/// Git: Get the subject line of a commit (or the current `HEAD`)
+pub(crate) fn get_commit_subject(commit: &str) -> Result<String> {
+    let repo = repo()?;
+    let object = repo.revparse_single(commit)?;
+    let commit = object.peel_to_commit()?;
+    Ok(commit.summary().unwrap_or_default().to_string())
 }
 
 fn trim_newline(s: &mut String) {
@@ -67,20 +90,57 @@ fn trim_newline(s: &mut String) {
     }
 }
 
+/// Walk every reachable commit, optionally abbreviating the ids to 7 chars.
+fn rev_list_all(abbrev: bool) -> Result<Vec<String>> {
+    let repo = repo()?;
+    let mut walk = repo.revwalk()?;
+    walk.push_glob("refs/*")?;
+    let mut res = Vec::new();
+    for oid in walk {
+        let oid = oid?;
+        let id = oid.to_string();
+        res.push(if abbrev { id[..7].to_string() } else { id });
+    }
+    Ok(res)
+}
+
 /// Git: Get all valid commit-ids
-pub(crate) fn get_commit_ids() -> Option<Vec<String>> {
-    let result = Command::new("git")
-        .arg("rev-list")
-        .arg("--all")
-        .output()
-        .expect("Command failed");
-    if result.status.success() {
-        let s = to_string(result.stdout);
-        let res: Vec<String> = s.split('\n').map(|s: &str| s.to_string()).collect();
-        Some(res)
+pub(crate) fn get_commit_ids() -> Result<Vec<String>> {
+    rev_list_all(false)
+}
+
+/// Git: List the files a commit changed against its first parent.
+///
+/// Root commits (no parent) are treated as changing every file. Rename
+/// detection is enabled so both sides of a rename are reported.
+pub(crate) fn changed_files(commit: &str) -> Result<Vec<String>> {
+    let repo = repo()?;
+    let object = repo.revparse_single(commit)?;
+    let commit = object.peel_to_commit()?;
+    let tree = commit.tree()?;
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
     } else {
         None
-    }
+    };
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    diff.find_similar(None)?;
+    let mut files = Vec::new();
+    diff.foreach(
+        &mut |delta, _| {
+            for path in [delta.old_file().path(), delta.new_file().path()]
+                .into_iter()
+                .flatten()
+            {
+                files.push(path.display().to_string());
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    )?;
+    Ok(files)
 }
 
 /// Git: Get all valid commit-ids since and/or before a specific commit-id
@@ -88,95 +148,94 @@ pub(crate) fn get_commit_ids_since_before(
     since: Option<&str>,
     before: Option<&str>,
 ) -> Option<Vec<String>> {
-    let arg = match (since, before) {
+    let range = match (since, before) {
         (Some(since), Some(before)) => format!("{since}^..{before}"),
         (None, Some(before)) => format!("{before}^"),
         (Some(since), None) => format!("{since}^..HEAD"),
         _ => return None,
     };
-    let result = Command::new("git")
-        .arg("rev-list")
-        .arg(arg)
-        .arg("--abbrev-commit")
-        .output()
-        .expect("Command failed");
-    if result.status.success() {
-        let s = to_string(result.stdout);
-        let res: Vec<String> = s.split('\n').map(|s: &str| s.to_string()).collect();
-        Some(res)
+    let repo = repo().ok()?;
+    let mut walk = repo.revwalk().ok()?;
+    if range.contains("..") {
+        walk.push_range(&range).ok()?;
     } else {
-        None
+        walk.push(repo.revparse_single(&range).ok()?.id()).ok()?;
     }
+    let mut res = Vec::new();
+    for oid in walk {
+        let oid = oid.ok()?;
+        res.push(oid.to_string()[..7].to_string());
+    }
+    Some(res)
 }
 
-/// Git: Get all valid commit-ids in abbreviated 7-char form
-pub(crate) fn get_abbrev_commit_ids() -> Option<Vec<String>> {
-    let result = Command::new("git")
-        .arg("rev-list")
-        .arg("--all")
-        .arg("--abbrev-commit")
-        .output()
-        .expect("Command failed");
-    if result.status.success() {
-        let s = to_string(result.stdout);
-        let res: Vec<String> = s.split('\n').map(|s: &str| s.to_string()).collect();
-        Some(res)
-    } else {
-        None
+/// Git: Get the ordered commit range `good..bad` (oldest first)
+///
+/// Equivalent to `git rev-list --reverse good..bad`: the commits reachable from
+/// `bad` but not from `good`, returned oldest-first as full hashes.
+pub(crate) fn get_commit_range(good: &str, bad: &str) -> Result<Vec<String>> {
+    let repo = repo()?;
+    let mut walk = repo.revwalk()?;
+    walk.push_range(&format!("{good}..{bad}"))?;
+    let mut res = Vec::new();
+    for oid in walk {
+        res.push(oid?.to_string());
     }
+    res.reverse();
+    Ok(res)
+}
+
+/// Git: Get all valid commit-ids in abbreviated 7-char form
+pub(crate) fn get_abbrev_commit_ids() -> Result<Vec<String>> {
+    rev_list_all(true)
 }
 
 /// Git: Get all valid branches
-pub(crate) fn get_branches() -> Option<Vec<String>> {
-    let result = Command::new("git")
-        .arg("branch")
-        .arg("--all")
-        .output()
-        .expect("Command failed");
-    if result.status.success() {
-        let s = to_string(result.stdout);
-        let res: Vec<String> = s.split('\n').map(|s: &str| s[2..].to_string()).collect();
-        Some(res)
-    } else {
-        None
+pub(crate) fn get_branches() -> Result<Vec<String>> {
+    let repo = repo()?;
+    let mut res = Vec::new();
+    for branch in repo.branches(None)? {
+        let (branch, _) = branch?;
+        if let Some(name) = branch.name()? {
+            res.push(name.to_string());
+        }
     }
+    Ok(res)
 }
 
 /// Git: Get all valid tags
-pub(crate) fn get_tags() -> Option<Vec<String>> {
-    let result = Command::new("git")
-        .arg("tag")
-        .arg("--list")
-        .output()
-        .expect("Command failed");
-    if result.status.success() {
-        let out = to_string(result.stdout);
-        let res: Vec<String> = out.split('\n').map(|s: &str| s.to_string()).collect();
-        Some(res)
+pub(crate) fn get_tags() -> Result<Vec<String>> {
+    let repo = repo()?;
+    let tags = repo.tag_names(None)?;
+    let res: Vec<String> = tags.iter().flatten().map(|s| s.to_string()).collect();
+    if res.is_empty() {
+        // Preserve the historical "one empty element means no tags" contract.
+        Ok(vec![String::new()])
     } else {
-        None
+        Ok(res)
     }
 }
 
 /// Git: Check if git status is dirty
-pub(crate) fn is_git_dirty() -> std::io::Result<()> {
-    let st = Command::new("git").arg("diff").arg("--quiet").status()?;
-    if st.success() {
+pub(crate) fn is_git_dirty() -> Result<()> {
+    let repo = repo()?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    if statuses.is_empty() {
         debug!("Git state is clean");
         Ok(())
     } else {
         error!("Git state is dirty");
-        let err = Error::new(ErrorKind::Other, "Git is dirty");
-        Err(err)
+        Err(Error::Command("Git is dirty".to_string()))
     }
 }
 
 /// Check if hyperfine is installed
-pub(crate) fn hyperfine_installed() -> std::io::Result<()> {
+pub(crate) fn hyperfine_installed() -> Result<()> {
     let result = Command::new("which").arg("hyperfine").output()?;
     if !result.status.success() {
-        let err = Error::new(ErrorKind::Other, "Hyperfine not installed");
-        Err(err)
+        Err(Error::Command("Hyperfine not installed".to_string()))
     } else {
         debug!("Hyperfine is installed");
         Ok(())
@@ -194,21 +253,28 @@ pub(crate) fn cleanup(tempfilelist: Vec<String>, dir: TempDir) -> std::io::Resul
 }
 
 /// Transform byte vector to string
-pub(crate) fn to_string(msg: Vec<u8>) -> String {
-    let mut result = std::str::from_utf8(&msg).unwrap().to_string();
+pub(crate) fn to_string(msg: Vec<u8>) -> Result<String> {
+    let mut result = std::str::from_utf8(&msg)?.to_string();
     trim_newline(&mut result);
-    result
+    Ok(result)
 }
 
-/// Write json object to disk
-pub(crate) fn write_json_to_disk(json: Value) -> std::io::Result<()> {
-    let json_pp = serde_json::to_string_pretty(&json)?;
+/// Write a rendered report to stdout
+pub(crate) fn write_to_stdout(content: &str) -> std::io::Result<()> {
     let mut stdout = std::io::stdout().lock();
-    stdout.write_all(json_pp.as_bytes())?;
+    stdout.write_all(content.as_bytes())?;
     stdout.flush()?;
     Ok(())
 }
 
+/// Write a rendered report to a file on disk
+pub(crate) fn write_to_disk(path: &str, content: &str) -> std::io::Result<()> {
+    let mut f = File::create(path)?;
+    f.write_all(content.as_bytes())?;
+    f.flush()?;
+    Ok(())
+}
+
 /// Merge several hyperfine result json files to a single result json object
 pub(crate) fn merge_json_files(files: &[String]) -> std::io::Result<serde_json::Value> {
     debug!("Merging files: {files:?}");